@@ -7,6 +7,7 @@ pub enum Error {
     IO(io::Error),
     Utf8(OsString),
     Empty,
+    UnknownDefault(String),
 }
 
 /// Result type for all fallible operations in this crate.
@@ -22,6 +23,11 @@ impl Display for Error {
                 name.to_string_lossy(),
             ),
             Error::Empty => f.write_str("no source files found"),
+            Error::UnknownDefault(name) => write!(
+                f,
+                "default module `{}` was not found among the discovered submodules",
+                name,
+            ),
         }
     }
 }
@@ -0,0 +1,22 @@
+//! A minimal shell-style glob matcher used to filter submodule names.
+//!
+//! Supports `*` (any sequence, including empty) and `?` (any single character). There's
+//! intentionally no support for character classes or `**` - doko's filters are meant for simple
+//! name prefixes/suffixes, not full path globbing.
+
+/// Returns whether `text` matches `pattern`.
+pub(crate) fn matches(pattern: &str, text: &str) -> bool {
+    matches_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn matches_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') => {
+            matches_bytes(&pattern[1..], text)
+                || (!text.is_empty() && matches_bytes(pattern, &text[1..]))
+        }
+        Some(b'?') => !text.is_empty() && matches_bytes(&pattern[1..], &text[1..]),
+        Some(&c) => !text.is_empty() && text[0] == c && matches_bytes(&pattern[1..], &text[1..]),
+    }
+}
@@ -0,0 +1,580 @@
+//! Implementation of the procedural macros re-exported by the `doko` crate. Not meant to be
+//! depended on directly; add `doko` to your `Cargo.toml` instead.
+
+use crate::error::{Error, Result};
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, LitStr, ParenthesizedGenericArguments, Token};
+
+mod error;
+mod glob;
+
+/// Arguments to the proc_macro, after being fully parsed and structured.
+struct DokoArgs {
+    path: LitStr,
+    method: Ident,
+    signature: ParenthesizedGenericArguments,
+    /// The module to fall back to when dispatch is given a name that isn't found, set via a
+    /// trailing `default = "name"` argument.
+    default: Option<LitStr>,
+    /// Glob patterns a submodule's file name must match at least one of, set via one or more
+    /// trailing `include = "glob"` arguments. Every file is included if empty.
+    include: Vec<LitStr>,
+    /// Glob patterns that exclude a matching submodule, set via one or more trailing
+    /// `exclude = "glob"` arguments. Checked after `include`.
+    exclude: Vec<LitStr>,
+}
+
+impl Parse for DokoArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let method_name: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let signature = input.parse()?;
+
+        let mut default = None;
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: LitStr = input.parse()?;
+
+            if key == "default" {
+                default = Some(value);
+            } else if key == "include" {
+                include.push(value);
+            } else if key == "exclude" {
+                exclude.push(value);
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("unknown doko! argument `{}`", key),
+                ));
+            }
+        }
+
+        Ok(DokoArgs {
+            path,
+            method: Ident::new(&format!("{}", method_name.value()), Span::call_site()),
+            signature,
+            default,
+            include,
+            exclude,
+        })
+    }
+}
+
+/// All data needed to properly include and call a method in a particular submodule.
+struct SubmoduleData {
+    /// The full path of identifiers from the included directory down to this submodule, e.g.
+    /// `["project_euler", "s001"]`.
+    path: Vec<Ident>,
+    /// The dispatch key this submodule is registered under, e.g. `"project_euler/s001"`.
+    name: String,
+    include: TokenStream2,
+}
+
+/// Provides a function that can call some shared method in our included submodules by the modules
+/// name.
+///
+/// Usage of this macro starts by specifying the module whose submodules should be included, the
+/// name of the function shared between those submodules, and a type signature for the function.
+///
+/// ```ignore
+/// doko::doko!("src/utilities", "my_execute", (&str) -> u32);
+/// ```
+///
+/// Behind the scene, this includes all submodules directly inside of that module (i.e.
+/// `src/utilities/*.rs`), and constructs a function named `doko_<method_name>` that can be used to
+/// call the method with a submodule's name.
+///
+/// ```ignore
+/// let i: u32 = doko_my_execute("foo")("argument"); // Executes `utilities::foo::my_execute`
+/// ```
+///
+/// Subdirectories are descended into as well, and their leaves are registered under a namespaced
+/// key made up of the path of directories joined with `/`, e.g. `"project_euler/s001"` for
+/// `src/utilities/project_euler/s001.rs`.
+///
+/// A companion `doko_<method_name>_modules() -> &'static [&'static str]` function is also
+/// generated, returning the sorted list of every module name the registry function accepts.
+///
+/// An optional trailing `default = "name"` argument names a submodule to fall back to when
+/// dispatch is given an unrecognized name, instead of panicking.
+///
+/// Optional trailing `include = "glob"` / `exclude = "glob"` arguments (either may repeat) filter
+/// which source files become submodules, matched against each file's name. A file is included if
+/// it matches at least one `include` glob (or no `include` globs were given), and matches none of
+/// the `exclude` globs.
+///
+/// A `doko_<method_name>_registry() -> doko::Registry<fn(#args) #return_type>` function is also
+/// generated, wrapping the same name-to-function mapping as a first-class `Registry` value that
+/// can be stored, passed around, or merged, instead of being re-dispatched through a match
+/// statement every time.
+#[proc_macro]
+pub fn doko(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DokoArgs);
+    match tokens_for_input(input, true, false) {
+        Ok(tokens) => tokens,
+        Err(err) => TokenStream::from(syn::Error::new(Span::call_site(), err).to_compile_error()),
+    }
+}
+
+/// Provides a function that can call some shared method in our included submodules by the modules
+/// name. Skips including the modules, to allow for calling multiple shared methods per module.
+#[proc_macro]
+pub fn doko_skip_mods(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DokoArgs);
+    match tokens_for_input(input, false, false) {
+        Ok(tokens) => tokens,
+        Err(err) => TokenStream::from(syn::Error::new(Span::call_site(), err).to_compile_error()),
+    }
+}
+
+/// Like [`doko!`], but the generated function returns `Option<fn(#args) #return_type>` instead of
+/// panicking when given an unrecognized module name.
+#[proc_macro]
+pub fn doko_try(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DokoArgs);
+    match tokens_for_input(input, true, true) {
+        Ok(tokens) => tokens,
+        Err(err) => TokenStream::from(syn::Error::new(Span::call_site(), err).to_compile_error()),
+    }
+}
+
+/// Like [`doko_skip_mods!`], but the generated function returns `Option<fn(#args) #return_type>`
+/// instead of panicking when given an unrecognized module name.
+#[proc_macro]
+pub fn doko_try_skip_mods(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DokoArgs);
+    match tokens_for_input(input, false, true) {
+        Ok(tokens) => tokens,
+        Err(err) => TokenStream::from(syn::Error::new(Span::call_site(), err).to_compile_error()),
+    }
+}
+
+/// Perform the heavy lifting for the macro. Does all the actual work whereas the [`doko!`] just
+/// parses input and checks the `Result` returned by this function.
+fn tokens_for_input(input: DokoArgs, include_mods: bool, fallible: bool) -> Result<TokenStream> {
+    let include: Vec<String> = input.include.iter().map(LitStr::value).collect();
+    let exclude: Vec<String> = input.exclude.iter().map(LitStr::value).collect();
+
+    let enclosing_modules = get_enclosing_modules(&input.path.value())?;
+    let (inner_includes, submod_data) =
+        get_submodule_data(&input.path.value(), &include, &exclude)?;
+    let outer_mod = wrap_enclosing_modules(inner_includes, &enclosing_modules);
+
+    let registry = build_registry(
+        &submod_data,
+        &enclosing_modules,
+        &input.method,
+        &input.signature,
+        input.default.as_ref(),
+        fallible,
+    )?;
+    let modules_list = build_modules_list(&submod_data, &input.method);
+    let registry_value = build_registry_value(
+        &submod_data,
+        &enclosing_modules,
+        &input.method,
+        &input.signature,
+    );
+
+    if include_mods {
+        Ok(TokenStream::from(quote! {
+             #outer_mod
+             #registry
+             #modules_list
+             #registry_value
+        }))
+    } else {
+        Ok(TokenStream::from(quote! {
+             #registry
+             #modules_list
+             #registry_value
+        }))
+    }
+}
+
+/// For the directory being included, returns a Vec containing the identifiers of all the modules
+/// the directory's submodules are enclosed in.
+///
+/// For instance, if the directory being included is `src/foo/bar/baz/<module.rs files>`, this
+/// function will return vec!['foo', 'bar', 'baz'].
+///
+/// Returns an Error if any of the path components can't be parsed into a valid UTF-8 string.
+fn get_enclosing_modules<P: AsRef<Path>>(directory: &P) -> Result<Vec<Ident>> {
+    directory
+        .as_ref()
+        .components()
+        .skip(1) // There's always a root folder, i.e. src, tests, or examples
+        .map(|section| {
+            let section_name = section
+                .as_os_str()
+                .to_str()
+                .ok_or(Error::Utf8(section.as_os_str().to_os_string()))?;
+            Ok(Ident::new(section_name, Span::call_site()))
+        })
+        .collect()
+}
+
+/// For the directory being included, recursively walks it and returns the `mod` declarations
+/// needed to include every submodule found, along with metadata for each of them.
+///
+/// For instance, if the directory being included is `src/foo/bar/` and it contains `a.rs`,
+/// `b.rs`, and a subdirectory `baz/c.rs`, this function will return metadata for the submodules
+/// `foo::bar::a`, `foo::bar::b`, and `foo::bar::baz::c`, dispatched under the keys `"a"`, `"b"`,
+/// and `"baz/c"` respectively.
+///
+/// Returns an Error if any of the submodules' absolute paths can't be parsed into UTF-8, or if no
+/// submodules are found anywhere in the tree (after applying `include`/`exclude` filters).
+fn get_submodule_data<P: AsRef<Path> + AsRef<OsStr>>(
+    directory: &P,
+    include: &[String],
+    exclude: &[String],
+) -> Result<(TokenStream2, Vec<SubmoduleData>)> {
+    let dir = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(manifest_dir) => PathBuf::from(manifest_dir).join(directory),
+        None => PathBuf::from(directory),
+    };
+
+    let (includes, submodules) = scan_directory(&dir, &[], include, exclude)?;
+    if submodules.is_empty() {
+        return Err(Error::Empty);
+    }
+
+    Ok((includes, submodules))
+}
+
+/// Walks `dir`, returning the combined `mod` declarations needed to include everything found
+/// directly inside of it plus metadata for every leaf submodule in the tree rooted at `dir`
+/// (`prefix` being the path of identifiers already enclosing `dir`).
+///
+/// A subdirectory containing its own `mod.rs` is treated as an already-declared module: it's
+/// `mod`-ed in directly, rather than being re-wrapped in a generated `pub mod { .. }` block, but
+/// is still walked so its leaves can be registered.
+///
+/// Files are additionally filtered against `include`/`exclude` globs (see [`passes_filters`])
+/// before becoming submodules; subdirectories are always walked regardless.
+fn scan_directory(
+    dir: &Path,
+    prefix: &[Ident],
+    include: &[String],
+    exclude: &[String],
+) -> Result<(TokenStream2, Vec<SubmoduleData>)> {
+    let mut file_names = Vec::new();
+    let mut dir_names = Vec::new();
+    let mut failures = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let entry_name = entry.file_name();
+
+        if file_type.is_dir() {
+            match entry_name.into_string() {
+                Ok(utf8) => dir_names.push(utf8),
+                Err(non_utf8) => failures.push(non_utf8),
+            }
+            continue;
+        }
+
+        if !file_type.is_file() {
+            continue;
+        }
+
+        if entry_name == "mod.rs" || entry_name == "lib.rs" || entry_name == "main.rs" {
+            continue;
+        }
+
+        let path = Path::new(&entry_name);
+        if path.extension() == Some(OsStr::new("rs")) {
+            match entry_name.into_string() {
+                Ok(mut utf8) => {
+                    utf8.truncate(utf8.len() - ".rs".len());
+                    file_names.push(utf8);
+                }
+                Err(non_utf8) => failures.push(non_utf8),
+            }
+        }
+    }
+
+    failures.sort();
+    if let Some(failure) = failures.into_iter().next() {
+        return Err(Error::Utf8(failure));
+    }
+
+    file_names.sort();
+    file_names.retain(|name| passes_filters(name, include, exclude));
+    dir_names.sort();
+
+    let mut includes = TokenStream2::new();
+    let mut submodules = Vec::new();
+
+    for name in file_names {
+        let submod = data_for_submodule(name, prefix);
+        includes.extend(submod.include.clone());
+        submodules.push(submod);
+    }
+
+    for dir_name in dir_names {
+        let sub_dir = dir.join(&dir_name);
+        let already_declared = sub_dir.join("mod.rs").is_file();
+        let (dir_ident, dir_path_attr) = normalize_mod_name(&dir_name, already_declared);
+
+        let mut sub_prefix = prefix.to_vec();
+        sub_prefix.push(dir_ident.clone());
+
+        let (inner_includes, inner_submodules) =
+            scan_directory(&sub_dir, &sub_prefix, include, exclude)?;
+        submodules.extend(inner_submodules);
+
+        includes.extend(if already_declared {
+            quote! { #dir_path_attr pub mod #dir_ident; }
+        } else {
+            quote! { #dir_path_attr pub mod #dir_ident { #inner_includes } }
+        });
+    }
+
+    Ok((includes, submodules))
+}
+
+/// Returns whether a submodule's file name (without the `.rs` extension) should become a
+/// submodule: it must match at least one of `include` (or `include` must be empty), and none of
+/// `exclude`.
+fn passes_filters(name: &str, include: &[String], exclude: &[String]) -> bool {
+    let included = include.is_empty() || include.iter().any(|pattern| glob::matches(pattern, name));
+    let excluded = exclude.iter().any(|pattern| glob::matches(pattern, name));
+    included && !excluded
+}
+
+/// Gets all metadata needed to include and call a particular submodule. Performs normalization of
+/// hyphens in the module's name (and dispatch key), emitting an updated `mod` TokenStream if such
+/// normalization is needed.
+fn data_for_submodule(name: String, prefix: &[Ident]) -> SubmoduleData {
+    let (ident, include) = if name.contains('-') {
+        let file_path = format!("{}.rs", name);
+        let normalized = name.replace('-', "_");
+        let ident = Ident::new(&normalized, Span::call_site());
+        (
+            ident.clone(),
+            quote! {
+                #[path = #file_path]
+                pub mod #ident;
+            },
+        )
+    } else {
+        let ident = Ident::new(&name, Span::call_site());
+        (
+            ident.clone(),
+            quote! {
+                pub mod #ident;
+            },
+        )
+    };
+
+    let mut path = prefix.to_vec();
+    path.push(ident);
+
+    let mut key_parts: Vec<String> = prefix.iter().map(Ident::to_string).collect();
+    key_parts.push(name.replace('-', "_"));
+
+    SubmoduleData {
+        path,
+        name: key_parts.join("/"),
+        include,
+    }
+}
+
+/// Normalizes a directory name into a valid identifier, returning the identifier along with a
+/// `#[path = "..."]` attribute TokenStream if the name needed normalization (i.e. contained a
+/// hyphen) and is otherwise empty.
+///
+/// `already_declared` distinguishes the two call sites in [`scan_directory`]: a directory that
+/// already has its own `mod.rs` is `mod`-ed in bodyless (`pub mod foo;`), so `#[path]` must name
+/// that file (`"foo-dir/mod.rs"`) rather than the directory itself, which rustc would otherwise
+/// try (and fail) to open as a file. A directory we're generating an inline `{ .. }` body for
+/// needs only the bare directory name, since `#[path]` on a `mod` *with* a body sets the
+/// directory its out-of-line children are resolved against.
+fn normalize_mod_name(name: &str, already_declared: bool) -> (Ident, TokenStream2) {
+    if name.contains('-') {
+        let normalized = name.replace('-', "_");
+        let ident = Ident::new(&normalized, Span::call_site());
+        let path_value = if already_declared {
+            format!("{}/mod.rs", name)
+        } else {
+            name.to_string()
+        };
+        let dir_path = LitStr::new(&path_value, Span::call_site());
+        (ident, quote! { #[path = #dir_path] })
+    } else {
+        (Ident::new(name, Span::call_site()), TokenStream2::new())
+    }
+}
+
+/// Wraps a TokenStream of `mod` declarations in the supermodules they are included in, in order.
+fn wrap_enclosing_modules(inner_modules: TokenStream2, enclosing_modules: &Vec<Ident>) -> TokenStream2 {
+    enclosing_modules
+        .iter()
+        .rev()
+        .fold(inner_modules, |stream, module| {
+            TokenStream2::from(quote!( pub mod #module { #stream } ))
+        })
+}
+
+/// Builds the `crate::foo::bar` path prefix leading to the submodules, from the identifiers of
+/// their enclosing modules.
+fn crate_prefix(enclosing_modules: &Vec<Ident>) -> TokenStream2 {
+    enclosing_modules
+        .iter()
+        .fold(quote! { crate }, |ident, next| quote! { #ident::#next })
+}
+
+/// Constructs a TokenStream for a function that can call some shared method in our included
+/// submodules by the modules name (as an &str).
+///
+/// Ordinarily, the output function's signature is `pub fn doko_<method>(module_name: &str) -> fn(#args)
+/// #return_type`, and an unrecognized `module_name` panics. If `fallible` is set, the function
+/// instead returns `Option<fn(#args) #return_type>`, yielding `None` for an unrecognized name. If
+/// `default` is set, it takes priority over both: an unrecognized name falls back to calling the
+/// named submodule instead of panicking or returning `None`.
+fn build_registry(
+    submodules: &Vec<SubmoduleData>,
+    enclosing_modules: &Vec<Ident>,
+    method: &Ident,
+    signature: &ParenthesizedGenericArguments,
+    default: Option<&LitStr>,
+    fallible: bool,
+) -> Result<TokenStream2> {
+    let gen_method_name = format_ident!("doko_{}", method);
+    let args = &signature.inputs;
+    let return_type = &signature.output;
+    let prefix = crate_prefix(enclosing_modules);
+    let calls = TokenStream2::from_iter(
+        submodules
+            .iter()
+            .map(|submod| get_call_for_submodule(submod, &prefix, method, fallible)),
+    );
+
+    let fallback = match default {
+        Some(default) => {
+            let submod = submodules
+                .iter()
+                .find(|submod| submod.name == default.value())
+                .ok_or_else(|| Error::UnknownDefault(default.value()))?;
+            let call = call_expr_for_submodule(submod, &prefix, method);
+            if fallible {
+                quote! { _ => Some(#call), }
+            } else {
+                quote! { _ => #call, }
+            }
+        }
+        None if fallible => quote! { _ => None, },
+        None => quote! { unknown => panic!("unknown module: {}", unknown), },
+    };
+
+    let output_type = if fallible {
+        quote! { Option<fn(#args) #return_type> }
+    } else {
+        quote! { fn(#args) #return_type }
+    };
+
+    Ok(TokenStream2::from(quote!(
+        pub fn #gen_method_name(module_name: &str) -> #output_type {
+            match module_name {
+                #calls
+                #fallback
+            }
+        }
+    )))
+}
+
+/// Constructs a TokenStream for a function listing every module name the registry function can
+/// dispatch to, sorted so the output is stable across builds.
+///
+/// The output function's signature is `pub fn doko_<method>_modules() -> &'static [&'static str]`.
+fn build_modules_list(submodules: &Vec<SubmoduleData>, method: &Ident) -> TokenStream2 {
+    let gen_fn_name = format_ident!("doko_{}_modules", method);
+    let mut names: Vec<&str> = submodules.iter().map(|submod| submod.name.as_str()).collect();
+    names.sort_unstable();
+    let names = names
+        .into_iter()
+        .map(|name| LitStr::new(name, Span::call_site()));
+
+    TokenStream2::from(quote!(
+        pub fn #gen_fn_name() -> &'static [&'static str] {
+            &[#(#names),*]
+        }
+    ))
+}
+
+/// Constructs a TokenStream for a function producing a `doko::Registry` of every discovered
+/// submodule, keyed the same way as the match-based registry function but reusable as a
+/// first-class value.
+///
+/// The output function's signature is
+/// `pub fn doko_<method>_registry() -> doko::Registry<fn(#args) #return_type>`.
+fn build_registry_value(
+    submodules: &Vec<SubmoduleData>,
+    enclosing_modules: &Vec<Ident>,
+    method: &Ident,
+    signature: &ParenthesizedGenericArguments,
+) -> TokenStream2 {
+    let gen_fn_name = format_ident!("doko_{}_registry", method);
+    let args = &signature.inputs;
+    let return_type = &signature.output;
+    let prefix = crate_prefix(enclosing_modules);
+
+    let mut sorted: Vec<&SubmoduleData> = submodules.iter().collect();
+    sorted.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let entries = sorted.into_iter().map(|submod| {
+        let key = LitStr::new(&submod.name, Span::call_site());
+        let call = call_expr_for_submodule(submod, &prefix, method);
+        quote! { (#key, #call as fn(#args) #return_type) }
+    });
+
+    TokenStream2::from(quote!(
+        pub fn #gen_fn_name() -> ::doko::Registry<fn(#args) #return_type> {
+            ::doko::Registry::from_sorted_entries(&[#(#entries),*])
+        }
+    ))
+}
+
+/// Builds the match arm for a particular submodule in our "registry" function by combining various
+/// pieces of metadata about the submodule. When `fallible`, the call is wrapped in `Some(..)` to
+/// match the registry function's `Option`-returning signature.
+fn get_call_for_submodule(
+    submod: &SubmoduleData,
+    prefix: &TokenStream2,
+    method: &Ident,
+    fallible: bool,
+) -> TokenStream2 {
+    let key = LitStr::new(&submod.name, Span::call_site());
+    let call = call_expr_for_submodule(submod, prefix, method);
+    if fallible {
+        quote! { #key => Some(#call), }
+    } else {
+        quote! { #key => #call, }
+    }
+}
+
+/// Builds the expression that calls a particular submodule's shared method, e.g.
+/// `crate::utilities::foo::run`.
+fn call_expr_for_submodule(
+    submod: &SubmoduleData,
+    prefix: &TokenStream2,
+    method: &Ident,
+) -> TokenStream2 {
+    let path = &submod.path;
+    quote! { #prefix::#(#path::)*#method }
+}
@@ -1,4 +1,8 @@
 // Usage: cargo run --example euler <problem numbers>
+//
+// A problem number may be prefixed with a subdirectory, joined by `/`, to reach a solution
+// nested under `examples/solutions`, e.g. `project_euler/1` for
+// `examples/solutions/project_euler/s001.rs`.
 
 use std::env;
 
@@ -8,13 +12,23 @@ fn main() {
     let args = env::args().skip(1).collect::<Vec<_>>();
     if args.is_empty() {
         println!("Usage: cargo run --example euler <problem numbers>");
+        println!("Available problems: {}", doko_solve_modules().join(", "));
         return;
     }
 
     for argument in args {
-        let problem_number = format!("{:03}", argument.parse::<u32>().unwrap());
-        let module = format!("s{}", problem_number);
+        let (subdir, number) = match argument.rsplit_once('/') {
+            Some((subdir, number)) => (Some(subdir), number),
+            None => (None, argument.as_str()),
+        };
+        let problem_number = format!("{:03}", number.parse::<u32>().unwrap());
+        let module = match subdir {
+            // Dispatch keys normalize hyphens to underscores, same as the generated module
+            // identifiers, so a hyphenated subdir name has to be normalized here too.
+            Some(subdir) => format!("{}/s{}", subdir.replace('-', "_"), problem_number),
+            None => format!("s{}", problem_number),
+        };
         let solution = doko_solve(&module)();
-        println!("Solution [{}]: {}", problem_number, solution);
+        println!("Solution [{}]: {}", module, solution);
     }
 }
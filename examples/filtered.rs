@@ -0,0 +1,10 @@
+// Usage: cargo run --example filtered
+
+doko::doko!("examples/filtereddir", "run", () -> &'static str, include = "a*", exclude = "*_test");
+
+fn main() {
+    // Only `alpha.rs` passes both filters: `beta.rs` doesn't match `include`, and
+    // `alpha_test.rs` is dropped by `exclude` despite matching it.
+    println!("Modules: {}", doko_run_modules().join(", "));
+    println!("{}", doko_run("alpha")());
+}
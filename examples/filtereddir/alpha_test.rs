@@ -0,0 +1,4 @@
+// Matches `include = "a*"` but is dropped by `exclude = "*_test"`; never `mod`-ed in.
+pub fn run() -> &'static str {
+    "alpha_test"
+}
@@ -0,0 +1,4 @@
+// Doesn't match `include = "a*"`, so it's dropped before `exclude` is even checked.
+pub fn run() -> &'static str {
+    "beta"
+}
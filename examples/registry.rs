@@ -0,0 +1,19 @@
+// Usage: cargo run --example registry
+
+doko::doko!("examples/registrydir", "value", () -> u32);
+
+fn main() {
+    let registry = doko_value_registry();
+
+    println!(
+        "{} modules: {}",
+        registry.len(),
+        registry.names().collect::<Vec<_>>().join(", ")
+    );
+    for (name, value) in registry.iter() {
+        println!("{}: {}", name, value());
+    }
+
+    println!("get(\"one\") = {:?}", registry.get("one").map(|value| value()));
+    println!("get(\"missing\") = {:?}", registry.get("missing").map(|value| value()));
+}
@@ -0,0 +1,4 @@
+// A hand-maintained mod.rs, unlike the rest of `solutions`: exercises `doko!` picking up a
+// hyphenated, already-declared subdirectory (`#[path = "extra-problems/mod.rs"]`) rather than
+// generating an inline module body for it.
+pub mod s100;
@@ -0,0 +1,3 @@
+pub fn solve() -> String {
+    "not a real Euler problem, just here to exercise the hyphenated already-declared case".to_string()
+}
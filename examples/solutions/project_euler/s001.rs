@@ -0,0 +1,9 @@
+pub fn solve() -> String {
+    let mut total = 0;
+    for n in 1..1000 {
+        if n % 3 == 0 || n % 5 == 0 {
+            total = total + n;
+        }
+    }
+    total.to_string()
+}
@@ -0,0 +1,14 @@
+// Usage: cargo run --example try
+
+doko::doko!("examples/trydir", "run", () -> &'static str, default = "a");
+doko::doko_try_skip_mods!("examples/trydir", "maybe_run", () -> &'static str);
+
+fn main() {
+    // An unrecognized name falls back to the configured default instead of panicking.
+    println!("{}", doko_run("b")());
+    println!("{}", doko_run("nonexistent")());
+
+    // Without a default, the _try variant returns an Option instead of panicking.
+    println!("{:?}", doko_maybe_run("b").map(|f| f()));
+    println!("{:?}", doko_maybe_run("nonexistent").map(|f| f()));
+}
@@ -0,0 +1,7 @@
+pub fn run() -> &'static str {
+    "A"
+}
+
+pub fn maybe_run() -> &'static str {
+    "maybe A"
+}
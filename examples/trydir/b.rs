@@ -0,0 +1,7 @@
+pub fn run() -> &'static str {
+    "B"
+}
+
+pub fn maybe_run() -> &'static str {
+    "maybe B"
+}
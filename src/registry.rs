@@ -0,0 +1,47 @@
+//! Runtime support for the `doko_<method>_registry()` function generated by [`doko!`](crate::doko!).
+
+/// A name-to-function-pointer map generated by `doko!`'s `_registry` variant.
+///
+/// Backed by a name-sorted static slice, so [`get`](Registry::get) is an `O(log n)` binary
+/// search rather than the linear scan a generated `match` would otherwise become for a directory
+/// with hundreds of modules.
+pub struct Registry<T: 'static> {
+    entries: &'static [(&'static str, T)],
+}
+
+impl<T: 'static + Copy> Registry<T> {
+    /// Builds a `Registry` from a slice already sorted by name. Only doko's generated code should
+    /// call this directly; an unsorted slice breaks [`get`](Registry::get).
+    #[doc(hidden)]
+    pub const fn from_sorted_entries(entries: &'static [(&'static str, T)]) -> Self {
+        Registry { entries }
+    }
+
+    /// Looks up the function registered under `name`, if any.
+    pub fn get(&self, name: &str) -> Option<T> {
+        self.entries
+            .binary_search_by_key(&name, |(key, _)| *key)
+            .ok()
+            .map(|index| self.entries[index].1)
+    }
+
+    /// Every registered name, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|(name, _)| *name)
+    }
+
+    /// Every registered `(name, function)` pair, in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, T)> + '_ {
+        self.entries.iter().map(|(name, function)| (*name, *function))
+    }
+
+    /// The number of registered entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the registry has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}